@@ -1,21 +1,32 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
 use dec_utils::dec_to_string_or_empty;
 use rust_decimal::prelude::*;
 //use rust_decimal_macros::dec;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_utc_time_ms::{de_string_to_utc_time_ms, se_time_ms_to_utc_z_string};
+use strum_macros::{Display as StrumDisplay, EnumString};
 use time_ms_conversions::time_ms_to_utc_string;
 
-#[derive(Debug, Deserialize, Serialize, Clone, Ord, Eq, PartialEq, PartialOrd)]
+pub mod store;
+pub mod stream_import;
+pub mod transfer_match;
+
+#[derive(Debug, Clone, Ord, Eq, PartialEq, PartialOrd, EnumString, StrumDisplay)]
 // As the second field this will be used to order records with the same time
+//
+// FromStr/Display are derived via strum so the exact CSV spelling ("Transfer In",
+// "Gift Send", ...) round-trips, and any label TaxBit adds (or a vendor-specific
+// one) falls through to `Unknown` instead of failing deserialization, preserving
+// the raw string so no data is lost.
 pub enum TaxBitRecType {
     Income,
 
-    #[serde(rename = "Transfer In")]
+    #[strum(serialize = "Transfer In")]
     TransferIn,
 
-    #[serde(rename = "Gift Received")]
+    #[strum(serialize = "Gift Received")]
     GiftReceived,
 
     Buy,
@@ -23,13 +34,34 @@ pub enum TaxBitRecType {
     Sale,
     Expense,
 
-    #[serde(rename = "Transfer Out")]
+    #[strum(serialize = "Transfer Out")]
     TransferOut,
 
-    #[serde(rename = "Gift Send")]
+    #[strum(serialize = "Gift Send")]
     GiftSent,
 
-    Unknown,
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl Serialize for TaxBitRecType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TaxBitRecType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        // Infallible: unrecognized labels fall through to `Unknown(s)`.
+        Ok(TaxBitRecType::from_str(&s).unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -81,7 +113,7 @@ impl Display for TaxBitRec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{},{:?},{},{},{},{},{},{},{},{},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
             time_ms_to_utc_string(self.time),
             self.type_txs,
             dec_to_string_or_empty(self.sent_quantity),
@@ -102,7 +134,7 @@ impl TaxBitRec {
     pub fn new() -> TaxBitRec {
         TaxBitRec {
             time: 0i64,
-            type_txs: TaxBitRecType::Unknown,
+            type_txs: TaxBitRecType::Unknown("".to_owned()),
             sent_quantity: None,
             sent_currency: "".to_owned(),
             sending_source: "".to_owned(),
@@ -127,7 +159,7 @@ impl TaxBitRec {
             | TaxBitRecType::Income
             | TaxBitRecType::GiftReceived
             | TaxBitRecType::Trade => self.received_currency.as_str(),
-            TaxBitRecType::Unknown => panic!("SNH"),
+            TaxBitRecType::Unknown(_) => panic!("SNH"),
         }
     }
 }
@@ -142,7 +174,6 @@ impl Eq for TaxBitRec {}
 
 impl PartialEq for TaxBitRec {
     fn eq(&self, other: &Self) -> bool {
-        println!("eq");
         self.time == other.time
             && self.exchange_transaction_id == other.exchange_transaction_id
             && self.blockchain_transaction_hash == other.blockchain_transaction_hash
@@ -160,7 +191,6 @@ impl PartialEq for TaxBitRec {
 
 impl PartialOrd for TaxBitRec {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        println!("partial_cmp");
         match self.time.partial_cmp(&other.time) {
             Some(core::cmp::Ordering::Equal) => {}
             ord => return ord,
@@ -242,7 +272,7 @@ mod test {
     #[test]
     fn test_new() {
         let tbr = TaxBitRec::new();
-        assert_eq!(tbr.type_txs, TaxBitRecType::Unknown);
+        assert_eq!(tbr.type_txs, TaxBitRecType::Unknown("".to_owned()));
         assert_eq!(tbr.sent_quantity, None);
         assert_eq!(tbr.sent_currency, "".to_owned());
         assert_eq!(tbr.sending_source, "".to_owned());
@@ -401,7 +431,7 @@ mod test {
     fn test_get_asset_panic() {
         let tbr = TaxBitRec::new();
 
-        assert_eq!(tbr.type_txs, TaxBitRecType::Unknown);
+        assert_eq!(tbr.type_txs, TaxBitRecType::Unknown("".to_owned()));
         tbr.get_asset();
     }
 
@@ -445,4 +475,39 @@ mod test {
         tbr.received_currency = "ABC".to_owned();
         assert_eq!(tbr.get_asset(), "ABC");
     }
+
+    #[test]
+    fn test_type_txs_from_str_known() {
+        use std::str::FromStr;
+
+        assert_eq!(
+            TaxBitRecType::from_str("Transfer In").unwrap(),
+            TaxBitRecType::TransferIn
+        );
+        assert_eq!(
+            TaxBitRecType::from_str("Gift Send").unwrap(),
+            TaxBitRecType::GiftSent
+        );
+        assert_eq!(TaxBitRecType::from_str("Buy").unwrap(), TaxBitRecType::Buy);
+    }
+
+    #[test]
+    fn test_type_txs_from_str_unknown_preserves_raw() {
+        use std::str::FromStr;
+
+        let tt = TaxBitRecType::from_str("Staking Reward").unwrap();
+        assert_eq!(tt, TaxBitRecType::Unknown("Staking Reward".to_owned()));
+    }
+
+    #[test]
+    fn test_type_txs_display_round_trips_csv_spelling() {
+        assert_eq!(TaxBitRecType::TransferIn.to_string(), "Transfer In");
+        assert_eq!(TaxBitRecType::GiftReceived.to_string(), "Gift Received");
+        assert_eq!(TaxBitRecType::TransferOut.to_string(), "Transfer Out");
+        assert_eq!(TaxBitRecType::GiftSent.to_string(), "Gift Send");
+        assert_eq!(
+            TaxBitRecType::Unknown("Staking Reward".to_owned()).to_string(),
+            "Staking Reward"
+        );
+    }
 }
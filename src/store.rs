@@ -0,0 +1,185 @@
+// A sled-backed persistent store for TaxBitRec, keyed by time + exchange_transaction_id
+// + blockchain_transaction_hash.
+
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+use crate::TaxBitRec;
+
+#[derive(Debug)]
+pub enum TbrStoreError {
+    Sled(sled::Error),
+    Serde(serde_json::Error),
+    Csv(csv::Error),
+    // Two distinct records collided on the identity key.
+    IdentityConflict {
+        existing: Box<TaxBitRec>,
+        incoming: Box<TaxBitRec>,
+    },
+}
+
+impl fmt::Display for TbrStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TbrStoreError::Sled(e) => write!(f, "sled error: {e}"),
+            TbrStoreError::Serde(e) => write!(f, "serde error: {e}"),
+            TbrStoreError::Csv(e) => write!(f, "csv error: {e}"),
+            TbrStoreError::IdentityConflict { existing, incoming } => write!(
+                f,
+                "record with identity key already stored but differs: existing={existing}, incoming={incoming}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TbrStoreError {}
+
+impl From<sled::Error> for TbrStoreError {
+    fn from(e: sled::Error) -> Self {
+        TbrStoreError::Sled(e)
+    }
+}
+
+impl From<serde_json::Error> for TbrStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        TbrStoreError::Serde(e)
+    }
+}
+
+impl From<csv::Error> for TbrStoreError {
+    fn from(e: csv::Error) -> Self {
+        TbrStoreError::Csv(e)
+    }
+}
+
+pub struct TbrStore {
+    db: sled::Db,
+}
+
+impl TbrStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<TbrStore, TbrStoreError> {
+        let db = sled::open(path)?;
+        Ok(TbrStore { db })
+    }
+
+    fn identity_key(rec: &TaxBitRec) -> Vec<u8> {
+        let mut key = Vec::new();
+        key.extend_from_slice(&rec.time.to_be_bytes());
+        key.push(0);
+        key.extend_from_slice(rec.exchange_transaction_id.as_bytes());
+        key.push(0);
+        key.extend_from_slice(rec.blockchain_transaction_hash.as_bytes());
+        key
+    }
+
+    /// Returns `Ok(true)` if newly stored, `Ok(false)` if already present.
+    pub fn insert(&self, rec: &TaxBitRec) -> Result<bool, TbrStoreError> {
+        let key = Self::identity_key(rec);
+        if let Some(existing_bytes) = self.db.get(&key)? {
+            let existing: TaxBitRec = serde_json::from_slice(&existing_bytes)?;
+            if existing == *rec {
+                return Ok(false);
+            }
+            return Err(TbrStoreError::IdentityConflict {
+                existing: Box::new(existing),
+                incoming: Box::new(rec.clone()),
+            });
+        }
+        let value = serde_json::to_vec(rec)?;
+        self.db.insert(key, value)?;
+        Ok(true)
+    }
+
+    pub fn iter_sorted(&self) -> Result<Vec<TaxBitRec>, TbrStoreError> {
+        let mut recs = Vec::new();
+        for item in self.db.iter() {
+            let (_key, value) = item?;
+            recs.push(serde_json::from_slice::<TaxBitRec>(&value)?);
+        }
+        recs.sort();
+        Ok(recs)
+    }
+
+    /// Returns the number of records newly inserted.
+    pub fn resume_import<R: Read>(&self, reader: R) -> Result<usize, TbrStoreError> {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+        let mut inserted = 0;
+        for result in csv_reader.deserialize() {
+            let rec: TaxBitRec = result?;
+            if self.insert(&rec)? {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::{TbrStore, TbrStoreError};
+    use crate::{TaxBitRec, TaxBitRecType};
+
+    fn rec(time: i64, exchange_transaction_id: &str) -> TaxBitRec {
+        let mut tbr = TaxBitRec::new();
+        tbr.time = time;
+        tbr.type_txs = TaxBitRecType::Buy;
+        tbr.received_currency = "BTC".to_owned();
+        tbr.received_quantity = Some(dec!(1));
+        tbr.exchange_transaction_id = exchange_transaction_id.to_owned();
+        tbr
+    }
+
+    #[test]
+    fn test_insert_dedups_identical_record() {
+        let store = TbrStore::open(tempfile::tempdir().unwrap().path()).unwrap();
+        let r = rec(1_000, "tx-1");
+
+        assert!(store.insert(&r).unwrap());
+        assert!(!store.insert(&r).unwrap());
+
+        assert_eq!(store.iter_sorted().unwrap(), vec![r]);
+    }
+
+    #[test]
+    fn test_iter_sorted_orders_by_time() {
+        let store = TbrStore::open(tempfile::tempdir().unwrap().path()).unwrap();
+        let later = rec(2_000, "tx-2");
+        let earlier = rec(1_000, "tx-1");
+
+        store.insert(&later).unwrap();
+        store.insert(&earlier).unwrap();
+
+        assert_eq!(store.iter_sorted().unwrap(), vec![earlier, later]);
+    }
+
+    #[test]
+    fn test_insert_rejects_identity_key_collision_with_different_content() {
+        // Two distinct wallet-to-wallet transfers at the same millisecond,
+        // both with blank exchange_transaction_id/blockchain_transaction_hash.
+        let store = TbrStore::open(tempfile::tempdir().unwrap().path()).unwrap();
+
+        let mut btc_out = TaxBitRec::new();
+        btc_out.time = 1_000;
+        btc_out.type_txs = TaxBitRecType::TransferOut;
+        btc_out.sent_currency = "BTC".to_owned();
+        btc_out.sent_quantity = Some(dec!(1));
+
+        let mut eth_out = TaxBitRec::new();
+        eth_out.time = 1_000;
+        eth_out.type_txs = TaxBitRecType::TransferOut;
+        eth_out.sent_currency = "ETH".to_owned();
+        eth_out.sent_quantity = Some(dec!(5));
+
+        assert!(store.insert(&btc_out).unwrap());
+        assert!(matches!(
+            store.insert(&eth_out),
+            Err(TbrStoreError::IdentityConflict { .. })
+        ));
+
+        // The first record must still be there, untouched.
+        assert_eq!(store.iter_sorted().unwrap(), vec![btc_out]);
+    }
+}
@@ -0,0 +1,71 @@
+// Streams TaxBitRec parse results one CSV row at a time instead of loading
+// the whole file into memory.
+
+use std::io::Read;
+
+use futures::stream::{self, Stream};
+
+use crate::TaxBitRec;
+
+#[derive(Debug)]
+pub enum ImportItem {
+    Ok(Box<TaxBitRec>),
+    Err { line: u64, source: csv::Error },
+}
+
+pub fn import_stream<R>(reader: R) -> impl Stream<Item = ImportItem>
+where
+    R: Read + Unpin + Send + 'static,
+{
+    let mut csv_reader = csv::Reader::from_reader(reader);
+    let headers = csv_reader.headers().ok().cloned();
+
+    stream::unfold((csv_reader, headers), |(mut csv_reader, headers)| async move {
+        // csv_reader.position() reports the *next* record's position, so
+        // capture this record's start first and use it as a fallback.
+        let start_line = csv_reader.position().line();
+        let mut raw = csv::StringRecord::new();
+        let line_of = |raw: &csv::StringRecord| raw.position().map_or(start_line, |p| p.line());
+        match csv_reader.read_record(&mut raw) {
+            Ok(true) => {
+                let line = line_of(&raw);
+                let item = match raw.deserialize::<TaxBitRec>(headers.as_ref()) {
+                    Ok(rec) => ImportItem::Ok(Box::new(rec)),
+                    Err(source) => ImportItem::Err { line, source },
+                };
+                Some((item, (csv_reader, headers)))
+            }
+            Ok(false) => None,
+            Err(source) => {
+                let line = line_of(&raw);
+                Some((ImportItem::Err { line, source }, (csv_reader, headers)))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{executor::block_on, StreamExt};
+
+    use super::{import_stream, ImportItem};
+    use crate::TaxBitRecType;
+
+    const CSV: &str = "Date and Time,Transaction Type,Sent Quantity,Sent Currency,Sending Source,Received Quantity,Received Currency,Receiving Destination,Fee,Fee Currency,Exchange Transaction ID,Blockchain Transaction Hash\n\
+2021-01-01T00:00:00Z,Buy,,,,1,BTC,exchange,,,,tx-1\n\
+garbage,row,with,too,few,columns\n\
+2021-01-02T00:00:00Z,Transfer Out,1,BTC,exchange,,,,0.001,BTC,,tx-2\n";
+
+    #[test]
+    fn test_import_stream_continues_past_a_bad_row() {
+        let items: Vec<ImportItem> = block_on(import_stream(CSV.as_bytes()).collect());
+
+        assert_eq!(items.len(), 3);
+        assert!(matches!(&items[0], ImportItem::Ok(rec) if rec.type_txs == TaxBitRecType::Buy));
+        // Header is line 1, "Buy" is line 2, so the malformed row is line 3.
+        assert!(matches!(&items[1], ImportItem::Err { line, .. } if *line == 3));
+        assert!(
+            matches!(&items[2], ImportItem::Ok(rec) if rec.type_txs == TaxBitRecType::TransferOut)
+        );
+    }
+}
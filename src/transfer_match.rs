@@ -0,0 +1,259 @@
+// Pairs TransferOut/TransferIn and GiftSent/GiftReceived records representing
+// wallet-to-wallet moves, so tax logic can exclude them from gain/loss.
+
+use std::collections::{HashMap, VecDeque};
+
+use rust_decimal::Decimal;
+
+use crate::{TaxBitRec, TaxBitRecType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferMatch {
+    pub out_idx: usize,
+    pub in_idx: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferMatchConfig {
+    pub quantity_tolerance: Decimal,
+    pub max_window_ms: i64,
+}
+
+impl Default for TransferMatchConfig {
+    fn default() -> Self {
+        TransferMatchConfig {
+            quantity_tolerance: Decimal::new(1, 8),
+            max_window_ms: 3 * 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransferMatchResult {
+    pub matches: Vec<TransferMatch>,
+    pub unmatched: Vec<usize>,
+}
+
+// Unmatched out indices (TransferOut or GiftSent), queued per currency in time
+// order for FIFO matching.
+struct PendingOuts {
+    by_currency: HashMap<String, VecDeque<usize>>,
+}
+
+impl PendingOuts {
+    fn new() -> PendingOuts {
+        PendingOuts {
+            by_currency: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, currency: &str, idx: usize) {
+        self.by_currency
+            .entry(currency.to_owned())
+            .or_default()
+            .push_back(idx);
+    }
+
+    fn take_match(
+        &mut self,
+        currency: &str,
+        in_time: i64,
+        received_quantity: Decimal,
+        recs: &[TaxBitRec],
+        config: &TransferMatchConfig,
+    ) -> Option<usize> {
+        let queue = self.by_currency.get_mut(currency)?;
+        let pos = queue.iter().position(|&out_idx| {
+            let out = &recs[out_idx];
+            let Some(sent_quantity) = out.sent_quantity else {
+                return false;
+            };
+            if in_time < out.time || in_time - out.time > config.max_window_ms {
+                return false;
+            }
+            let fee = if out.fee_currency == out.sent_currency {
+                out.fee_quantity.unwrap_or_default()
+            } else {
+                Decimal::ZERO
+            };
+            (sent_quantity - fee - received_quantity).abs() <= config.quantity_tolerance
+        })?;
+        queue.remove(pos)
+    }
+}
+
+pub fn match_transfers(recs: &[TaxBitRec], config: &TransferMatchConfig) -> TransferMatchResult {
+    let mut order: Vec<usize> = (0..recs.len()).collect();
+    order.sort_by(|&a, &b| recs[a].cmp(&recs[b]));
+
+    let mut pending_transfers = PendingOuts::new();
+    let mut pending_gifts = PendingOuts::new();
+    let mut matched = vec![false; recs.len()];
+    let mut matches = Vec::new();
+
+    for idx in order {
+        let rec = &recs[idx];
+        match rec.type_txs {
+            TaxBitRecType::TransferOut if rec.sent_quantity.is_some() => {
+                pending_transfers.push(&rec.sent_currency, idx);
+            }
+            TaxBitRecType::GiftSent if rec.sent_quantity.is_some() => {
+                pending_gifts.push(&rec.sent_currency, idx);
+            }
+            TaxBitRecType::TransferIn | TaxBitRecType::GiftReceived => {
+                let Some(received_quantity) = rec.received_quantity else {
+                    continue;
+                };
+                let pending = if rec.type_txs == TaxBitRecType::TransferIn {
+                    &mut pending_transfers
+                } else {
+                    &mut pending_gifts
+                };
+                if let Some(out_idx) = pending.take_match(
+                    &rec.received_currency,
+                    rec.time,
+                    received_quantity,
+                    recs,
+                    config,
+                ) {
+                    matched[out_idx] = true;
+                    matched[idx] = true;
+                    matches.push(TransferMatch { out_idx, in_idx: idx });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let unmatched = (0..recs.len())
+        .filter(|&i| {
+            matches!(
+                recs[i].type_txs,
+                TaxBitRecType::TransferOut
+                    | TaxBitRecType::TransferIn
+                    | TaxBitRecType::GiftSent
+                    | TaxBitRecType::GiftReceived
+            ) && !matched[i]
+        })
+        .collect();
+
+    TransferMatchResult { matches, unmatched }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn rec(type_txs: TaxBitRecType, time: i64) -> TaxBitRec {
+        let mut tbr = TaxBitRec::new();
+        tbr.type_txs = type_txs;
+        tbr.time = time;
+        tbr
+    }
+
+    #[test]
+    fn test_matches_transfer_out_to_transfer_in() {
+        let mut out = rec(TaxBitRecType::TransferOut, 1_000);
+        out.sent_currency = "BTC".to_owned();
+        out.sent_quantity = Some(dec!(1));
+
+        let mut inn = rec(TaxBitRecType::TransferIn, 2_000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(1));
+
+        let recs = vec![out, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert_eq!(result.matches, vec![TransferMatch { out_idx: 0, in_idx: 1 }]);
+        assert!(result.unmatched.is_empty());
+    }
+
+    #[test]
+    fn test_matches_within_fee_tolerance() {
+        let mut out = rec(TaxBitRecType::TransferOut, 1_000);
+        out.sent_currency = "BTC".to_owned();
+        out.sent_quantity = Some(dec!(1));
+        out.fee_currency = "BTC".to_owned();
+        out.fee_quantity = Some(dec!(0.001));
+
+        let mut inn = rec(TaxBitRecType::TransferIn, 2_000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(0.999));
+
+        let recs = vec![out, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert_eq!(result.matches, vec![TransferMatch { out_idx: 0, in_idx: 1 }]);
+    }
+
+    #[test]
+    fn test_does_not_match_gift_to_transfer() {
+        let mut out = rec(TaxBitRecType::GiftSent, 1_000);
+        out.sent_currency = "BTC".to_owned();
+        out.sent_quantity = Some(dec!(1));
+
+        let mut inn = rec(TaxBitRecType::TransferIn, 2_000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(1));
+
+        let recs = vec![out, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert!(result.matches.is_empty());
+        assert_eq!(result.unmatched, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_none_quantity_never_matches() {
+        let out = rec(TaxBitRecType::TransferOut, 1_000);
+        let mut inn = rec(TaxBitRecType::TransferIn, 2_000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(1));
+
+        let recs = vec![out, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert!(result.matches.is_empty());
+        assert_eq!(result.unmatched, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_outside_window_does_not_match() {
+        let mut out = rec(TaxBitRecType::TransferOut, 0);
+        out.sent_currency = "BTC".to_owned();
+        out.sent_quantity = Some(dec!(1));
+
+        let mut inn = rec(TaxBitRecType::TransferIn, 30 * 24 * 60 * 60 * 1000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(1));
+
+        let recs = vec![out, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert!(result.matches.is_empty());
+        assert_eq!(result.unmatched, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_fifo_matches_earliest_out_first() {
+        let mut out_early = rec(TaxBitRecType::TransferOut, 1_000);
+        out_early.sent_currency = "BTC".to_owned();
+        out_early.sent_quantity = Some(dec!(1));
+
+        let mut out_late = rec(TaxBitRecType::TransferOut, 1_500);
+        out_late.sent_currency = "BTC".to_owned();
+        out_late.sent_quantity = Some(dec!(1));
+
+        let mut inn = rec(TaxBitRecType::TransferIn, 2_000);
+        inn.received_currency = "BTC".to_owned();
+        inn.received_quantity = Some(dec!(1));
+
+        let recs = vec![out_early, out_late, inn];
+        let result = match_transfers(&recs, &TransferMatchConfig::default());
+
+        assert_eq!(result.matches, vec![TransferMatch { out_idx: 0, in_idx: 2 }]);
+        assert_eq!(result.unmatched, vec![1]);
+    }
+}